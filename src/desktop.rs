@@ -0,0 +1,551 @@
+//! Linux `.desktop` file discovery and execution, following the subset of
+//! the [Desktop Entry Specification][spec] this launcher relies on: the
+//! `[Desktop Entry]` group, `Type`/`NoDisplay`/`Hidden` filtering, localized
+//! `Name[..]` matching, `TryExec` validation, and `Exec=` field-code
+//! expansion.
+//!
+//! [spec]: https://specifications.freedesktop.org/desktop-entry-spec/latest/
+
+use crate::runner::Runner;
+use crate::terminal::TerminalEmulator;
+use crate::SearchResult;
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// A parsed `[Desktop Entry]` group, before it's turned into a
+/// [`SearchResult`].
+struct DesktopEntry {
+    name: String,
+    exec: String,
+    try_exec: Option<String>,
+    icon: Option<String>,
+    terminal: bool,
+}
+
+/// The directories desktop entries are discovered in, in priority order.
+pub(crate) fn desktop_dirs() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from("/usr/share/applications"),
+        dirs::data_local_dir()
+            .map(|p| p.join("applications"))
+            .unwrap_or_else(|| PathBuf::from("~/.local/share/applications")),
+        PathBuf::from("/var/lib/flatpak/exports/share/applications"),
+        PathBuf::from("/snap/gui"),
+    ]
+}
+
+/// A desktop entry discovered while indexing a directory, already filtered
+/// by `Type`/`NoDisplay`/`Hidden`/`TryExec`.
+pub(crate) struct IndexedDesktopEntry {
+    pub display_name: String,
+    pub exec: String,
+    pub path: PathBuf,
+    pub terminal: bool,
+    pub icon: Option<String>,
+}
+
+/// Parses every launchable `.desktop` file under `dir`, for the application
+/// index (see `crate::index`) to cache.
+pub(crate) fn index_desktop_dir(dir: &Path) -> Vec<IndexedDesktopEntry> {
+    if !dir.exists() {
+        return Vec::new();
+    }
+
+    let walker = walkdir::WalkDir::new(dir).max_depth(2).follow_links(true);
+    walker
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "desktop"))
+        .filter_map(|e| {
+            let parsed = parse_desktop_file(e.path())?;
+            if let Some(try_exec) = &parsed.try_exec {
+                crate::path_resolve::resolve(try_exec)?;
+            }
+            Some(IndexedDesktopEntry {
+                display_name: parsed.name,
+                exec: parsed.exec,
+                path: e.path().to_path_buf(),
+                terminal: parsed.terminal,
+                icon: parsed.icon,
+            })
+        })
+        .collect()
+}
+
+/// Parses the `[Desktop Entry]` group of a `.desktop` file, honoring
+/// `Type`/`NoDisplay`/`Hidden` and picking the best-matching localized name
+/// for the user's locale.
+fn parse_desktop_file(path: &Path) -> Option<DesktopEntry> {
+    use std::io::BufRead;
+
+    let file = std::fs::File::open(path).ok()?;
+    let reader = std::io::BufReader::new(file);
+
+    let locale_keys = preferred_locale_keys();
+
+    let mut in_desktop_entry = false;
+    let mut seen_desktop_entry = false;
+    let mut entry_type: Option<String> = None;
+    let mut no_display = false;
+    let mut hidden = false;
+    let mut name: Option<String> = None;
+    let mut localized_name: Option<(usize, String)> = None;
+    let mut exec: Option<String> = None;
+    let mut try_exec: Option<String> = None;
+    let mut icon: Option<String> = None;
+    let mut terminal = false;
+
+    for line in reader.lines().map_while(Result::ok) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(group) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            if seen_desktop_entry {
+                // We've reached the group after [Desktop Entry]; stop here
+                // so action groups like [Desktop Action Foo] aren't parsed.
+                break;
+            }
+            in_desktop_entry = group == "Desktop Entry";
+            if in_desktop_entry {
+                seen_desktop_entry = true;
+            }
+            continue;
+        }
+
+        if !in_desktop_entry {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim();
+
+        if key == "Name" {
+            name = Some(value.to_string());
+        } else if let Some(locale) = key.strip_prefix("Name[").and_then(|s| s.strip_suffix(']')) {
+            if let Some(rank) = locale_keys.iter().position(|l| l == locale) {
+                let better = match &localized_name {
+                    Some((current_rank, _)) => rank < *current_rank,
+                    None => true,
+                };
+                if better {
+                    localized_name = Some((rank, value.to_string()));
+                }
+            }
+        } else if key == "Exec" {
+            exec = Some(value.to_string());
+        } else if key == "TryExec" {
+            try_exec = Some(value.to_string());
+        } else if key == "Icon" {
+            icon = Some(value.to_string());
+        } else if key == "Type" {
+            entry_type = Some(value.to_string());
+        } else if key == "NoDisplay" {
+            no_display = value.eq_ignore_ascii_case("true");
+        } else if key == "Hidden" {
+            hidden = value.eq_ignore_ascii_case("true");
+        } else if key == "Terminal" {
+            terminal = value.eq_ignore_ascii_case("true");
+        }
+    }
+
+    if !seen_desktop_entry {
+        return None;
+    }
+    if entry_type.as_deref().is_some_and(|t| t != "Application") {
+        return None;
+    }
+    if no_display || hidden {
+        return None;
+    }
+
+    let name = localized_name.map(|(_, n)| n).or(name)?;
+    let exec = exec?;
+
+    Some(DesktopEntry {
+        name,
+        exec,
+        try_exec,
+        icon,
+        terminal,
+    })
+}
+
+/// Builds the ordered list of `Name[..]` locale keys to try, most specific
+/// first, derived from `LC_MESSAGES` (falling back to `LANG`) per the
+/// Desktop Entry Specification's locale matching rules
+/// (`lang_COUNTRY@MODIFIER`, `lang_COUNTRY`, `lang@MODIFIER`, `lang`).
+fn preferred_locale_keys() -> Vec<String> {
+    let raw = std::env::var("LC_MESSAGES")
+        .or_else(|_| std::env::var("LANG"))
+        .unwrap_or_default();
+
+    // Strip the encoding (".UTF-8") but keep country/modifier; the modifier
+    // may follow the encoding, as in `lang_COUNTRY.ENCODING@MODIFIER`.
+    let raw = match raw.split_once('.') {
+        Some((head, rest)) => match rest.split_once('@') {
+            Some((_, modifier)) => format!("{head}@{modifier}"),
+            None => head.to_string(),
+        },
+        None => raw.clone(),
+    };
+    let raw = raw.as_str();
+    if raw.is_empty() || raw == "C" || raw == "POSIX" {
+        return Vec::new();
+    }
+
+    let (lang_country, modifier) = match raw.split_once('@') {
+        Some((l, m)) => (l, Some(m)),
+        None => (raw, None),
+    };
+    let (lang, country) = match lang_country.split_once('_') {
+        Some((l, c)) => (l, Some(c)),
+        None => (lang_country, None),
+    };
+
+    let mut keys = Vec::new();
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        keys.push(format!("{lang}_{country}@{modifier}"));
+    }
+    if let Some(country) = country {
+        keys.push(format!("{lang}_{country}"));
+    }
+    if let Some(modifier) = modifier {
+        keys.push(format!("{lang}@{modifier}"));
+    }
+    keys.push(lang.to_string());
+    keys
+}
+
+pub(crate) fn execute_desktop_entry(
+    exec: &str,
+    res: &SearchResult,
+    args: &[String],
+    use_terminal: bool,
+) -> Result<()> {
+    let display_name = res.display_name.as_deref().unwrap_or_default();
+    let argv = expand_field_codes(exec, display_name, res.icon.as_deref(), &res.path, args)?;
+
+    if argv.is_empty() {
+        bail!("Empty Exec line");
+    }
+
+    let argv = if use_terminal {
+        let emulator = TerminalEmulator::detect()
+            .context("Terminal=true entry or --terminal, but no terminal emulator could be found")?;
+        emulator.wrap(&argv[0], &argv[1..])
+    } else {
+        argv
+    };
+
+    Runner::new(&argv[0])
+        .args(&argv[1..])
+        .normalize_env()
+        .spawn_detached()
+}
+
+/// Expands the field codes in an `Exec=` line per the Desktop Entry
+/// Specification. The file-list codes (`%f`/`%u`/`%F`/`%U`) are filled in
+/// from `args` when present; if the `Exec=` line has none of them, `args`
+/// is appended to the command instead. The deprecated codes (`%d`/`%D`/
+/// `%n`/`%N`/`%v`/`%m`) are dropped, matching how a desktop environment launches an entry that
+/// carries no file/URL.
+fn expand_field_codes(
+    exec: &str,
+    display_name: &str,
+    icon: Option<&str>,
+    desktop_file: &Path,
+    args: &[String],
+) -> Result<Vec<String>> {
+    let mut out = Vec::new();
+    let mut consumed_args = false;
+
+    for token in split_exec(exec)? {
+        match token.as_str() {
+            "%f" | "%u" => {
+                consumed_args = true;
+                if let Some(first) = args.first() {
+                    out.push(first.clone());
+                }
+            }
+            "%F" | "%U" => {
+                consumed_args = true;
+                out.extend(args.iter().cloned());
+            }
+            "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => {
+                // Deprecated codes; nothing to substitute.
+            }
+            "%i" => {
+                if let Some(icon) = icon {
+                    out.push("--icon".to_string());
+                    out.push(icon.to_string());
+                }
+            }
+            _ => {
+                let expanded = token
+                    .replace("%c", display_name)
+                    .replace("%k", &desktop_file.to_string_lossy())
+                    .replace("%%", "%");
+                if !expanded.is_empty() {
+                    out.push(expanded);
+                }
+            }
+        }
+    }
+
+    if !consumed_args {
+        out.extend(args.iter().cloned());
+    }
+
+    Ok(out)
+}
+
+/// Tokenizes an `Exec=` line per the Desktop Entry Specification's quoting
+/// rules: double-quoted strings may escape `"`, `` ` ``, `$`, and `\`;
+/// unquoted whitespace separates arguments.
+fn split_exec(exec: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = exec.chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut token = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some(c @ ('"' | '`' | '$' | '\\')) => token.push(c),
+                        Some(c) => {
+                            token.push('\\');
+                            token.push(c);
+                        }
+                        None => bail!("Unterminated escape in Exec line: {exec}"),
+                    },
+                    Some(c) => token.push(c),
+                    None => bail!("Unterminated quoted string in Exec line: {exec}"),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+        }
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_exec_unquoted_tokens() {
+        let tokens = split_exec("firefox %u").unwrap();
+        assert_eq!(tokens, vec!["firefox", "%u"]);
+    }
+
+    #[test]
+    fn split_exec_quoted_token_with_space() {
+        let tokens = split_exec(r#"env "My App" --flag"#).unwrap();
+        assert_eq!(tokens, vec!["env", "My App", "--flag"]);
+    }
+
+    #[test]
+    fn split_exec_handles_escaped_characters() {
+        let tokens = split_exec(r#""say \"hi\" \$HOME \\ \`x\`""#).unwrap();
+        assert_eq!(tokens, vec![r#"say "hi" $HOME \ `x`"#]);
+    }
+
+    #[test]
+    fn split_exec_unterminated_quote_errors() {
+        assert!(split_exec(r#"app "unterminated"#).is_err());
+    }
+
+    #[test]
+    fn split_exec_unterminated_escape_errors() {
+        assert!(split_exec("app \"trailing\\").is_err());
+    }
+
+    #[test]
+    fn expand_field_codes_single_file_arg() {
+        let args = vec!["/tmp/a.txt".to_string()];
+        let out = expand_field_codes(
+            "gedit %f",
+            "Text Editor",
+            None,
+            Path::new("/usr/share/applications/gedit.desktop"),
+            &args,
+        )
+        .unwrap();
+        assert_eq!(out, vec!["gedit", "/tmp/a.txt"]);
+    }
+
+    #[test]
+    fn expand_field_codes_multi_file_arg_uses_all_args() {
+        let args = vec!["/tmp/a.txt".to_string(), "/tmp/b.txt".to_string()];
+        let out = expand_field_codes(
+            "editor %F",
+            "Editor",
+            None,
+            Path::new("/usr/share/applications/editor.desktop"),
+            &args,
+        )
+        .unwrap();
+        assert_eq!(out, vec!["editor", "/tmp/a.txt", "/tmp/b.txt"]);
+    }
+
+    #[test]
+    fn expand_field_codes_without_file_code_appends_args() {
+        let args = vec!["--flag".to_string()];
+        let out = expand_field_codes(
+            "app",
+            "App",
+            None,
+            Path::new("/usr/share/applications/app.desktop"),
+            &args,
+        )
+        .unwrap();
+        assert_eq!(out, vec!["app", "--flag"]);
+    }
+
+    #[test]
+    fn expand_field_codes_icon_and_name_and_path_and_percent() {
+        let out = expand_field_codes(
+            "app --icon %i --name %c --file %k --literal %%",
+            "My App",
+            Some("my-icon"),
+            Path::new("/usr/share/applications/app.desktop"),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            vec![
+                "app",
+                "--icon",
+                "--icon",
+                "my-icon",
+                "--name",
+                "My App",
+                "--file",
+                "/usr/share/applications/app.desktop",
+                "--literal",
+                "%",
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_field_codes_deprecated_codes_dropped() {
+        let out = expand_field_codes(
+            "app %d %D %n %N %v %m",
+            "App",
+            None,
+            Path::new("/usr/share/applications/app.desktop"),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(out, vec!["app"]);
+    }
+
+    #[test]
+    fn expand_field_codes_no_icon_drops_icon_flag() {
+        let out = expand_field_codes(
+            "app %i",
+            "App",
+            None,
+            Path::new("/usr/share/applications/app.desktop"),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(out, vec!["app"]);
+    }
+
+    // `LC_MESSAGES`/`LANG` are process-global, so tests that mutate them are
+    // serialized against each other rather than racing under `cargo test`'s
+    // default parallel test execution.
+    static LOCALE_ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Guards `LC_MESSAGES`/`LANG` for a test, restoring both afterward so
+    /// locale-dependent tests can't leak state into one another.
+    fn with_locale_env<T>(
+        lc_messages: Option<&str>,
+        lang: Option<&str>,
+        test: impl FnOnce() -> T,
+    ) -> T {
+        let _guard = LOCALE_ENV_LOCK.lock().unwrap();
+        let prev_lc_messages = std::env::var("LC_MESSAGES").ok();
+        let prev_lang = std::env::var("LANG").ok();
+
+        match lc_messages {
+            Some(v) => std::env::set_var("LC_MESSAGES", v),
+            None => std::env::remove_var("LC_MESSAGES"),
+        }
+        match lang {
+            Some(v) => std::env::set_var("LANG", v),
+            None => std::env::remove_var("LANG"),
+        }
+
+        let result = test();
+
+        match prev_lc_messages {
+            Some(v) => std::env::set_var("LC_MESSAGES", v),
+            None => std::env::remove_var("LC_MESSAGES"),
+        }
+        match prev_lang {
+            Some(v) => std::env::set_var("LANG", v),
+            None => std::env::remove_var("LANG"),
+        }
+
+        result
+    }
+
+    #[test]
+    fn preferred_locale_keys_full_locale() {
+        with_locale_env(Some("pt_BR@euro.UTF-8"), None, || {
+            assert_eq!(
+                preferred_locale_keys(),
+                vec!["pt_BR@euro", "pt_BR", "pt@euro", "pt"]
+            );
+        });
+    }
+
+    #[test]
+    fn preferred_locale_keys_falls_back_to_lang() {
+        with_locale_env(None, Some("de_DE.UTF-8"), || {
+            assert_eq!(preferred_locale_keys(), vec!["de_DE", "de"]);
+        });
+    }
+
+    #[test]
+    fn preferred_locale_keys_missing_env_is_empty() {
+        with_locale_env(None, None, || {
+            assert!(preferred_locale_keys().is_empty());
+        });
+    }
+
+    #[test]
+    fn preferred_locale_keys_posix_is_empty() {
+        with_locale_env(Some("C"), None, || {
+            assert!(preferred_locale_keys().is_empty());
+        });
+    }
+}
@@ -0,0 +1,228 @@
+//! Persistent application index, built from desktop entries and `$PATH`
+//! executables, so `launch_app` can fuzzy-match without re-scanning the
+//! filesystem on every invocation. The index is cached as JSON under
+//! `dirs::cache_dir()/rsopen/index.json` and kept fresh by comparing each
+//! source directory's mtime against what was recorded at the last scan.
+
+use crate::SearchResult;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use strsim::levenshtein;
+
+/// A single indexed entry: a desktop file or an executable on `$PATH`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedApp {
+    /// Lowercased key used for matching.
+    name: String,
+    display_name: String,
+    exec: Option<String>,
+    path: PathBuf,
+    is_desktop: bool,
+    terminal: bool,
+    icon: Option<String>,
+}
+
+/// Everything discovered under one source directory, tagged with that
+/// directory's mtime at scan time so a later run can tell whether it needs
+/// rescanning.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct DirBucket {
+    mtime: u64,
+    apps: Vec<IndexedApp>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct AppIndex {
+    dirs: HashMap<PathBuf, DirBucket>,
+}
+
+enum DirKind {
+    Desktop,
+    PathBin,
+}
+
+fn cache_file() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("rsopen").join("index.json"))
+}
+
+/// The directories the index is built from: desktop-entry directories on
+/// Linux, plus every directory on `$PATH`.
+fn source_dirs() -> Vec<(PathBuf, DirKind)> {
+    let mut dirs = Vec::new();
+
+    #[cfg(target_os = "linux")]
+    for dir in crate::desktop::desktop_dirs() {
+        dirs.push((dir, DirKind::Desktop));
+    }
+
+    if let Some(path_var) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path_var).map(|d| (d, DirKind::PathBin)));
+    }
+
+    dirs
+}
+
+fn dir_mtime(dir: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(dir).ok()?.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+fn scan_dir(dir: &Path, kind: &DirKind) -> Vec<IndexedApp> {
+    match kind {
+        DirKind::Desktop => {
+            #[cfg(target_os = "linux")]
+            {
+                crate::desktop::index_desktop_dir(dir)
+                    .into_iter()
+                    .map(|e| IndexedApp {
+                        name: e.display_name.to_lowercase(),
+                        display_name: e.display_name,
+                        exec: Some(e.exec),
+                        path: e.path,
+                        is_desktop: true,
+                        terminal: e.terminal,
+                        icon: e.icon,
+                    })
+                    .collect()
+            }
+            #[cfg(not(target_os = "linux"))]
+            {
+                Vec::new()
+            }
+        }
+        DirKind::PathBin => scan_executable_dir(dir),
+    }
+}
+
+fn scan_executable_dir(dir: &Path) -> Vec<IndexedApp> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    read_dir
+        .filter_map(|e| e.ok())
+        .filter(|e| crate::path_resolve::is_executable(&e.path()))
+        .map(|e| {
+            let name = e.file_name().to_string_lossy().to_lowercase();
+            IndexedApp {
+                name: name.clone(),
+                display_name: name,
+                exec: None,
+                path: e.path(),
+                is_desktop: false,
+                terminal: false,
+                icon: None,
+            }
+        })
+        .collect()
+}
+
+fn load() -> Option<AppIndex> {
+    let data = std::fs::read_to_string(cache_file()?).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+fn save(index: &AppIndex) -> Result<()> {
+    let file = cache_file().context("Could not determine cache directory")?;
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let data = serde_json::to_string_pretty(index)?;
+    std::fs::write(file, data)?;
+    Ok(())
+}
+
+/// Loads the cached index, rescanning any directory whose mtime has
+/// changed (or that isn't cached at all), then persists the result.
+/// `force` rescans every directory unconditionally (`rsopen --reindex`).
+fn load_or_refresh(force: bool) -> AppIndex {
+    let mut index = if force {
+        AppIndex::default()
+    } else {
+        load().unwrap_or_default()
+    };
+
+    let mut changed = force;
+    let mut live_dirs = HashSet::new();
+    for (dir, kind) in source_dirs() {
+        let Some(mtime) = dir_mtime(&dir) else {
+            continue;
+        };
+        live_dirs.insert(dir.clone());
+
+        let up_to_date = index
+            .dirs
+            .get(&dir)
+            .is_some_and(|bucket| bucket.mtime == mtime);
+        if up_to_date {
+            continue;
+        }
+
+        let apps = scan_dir(&dir, &kind);
+        index.dirs.insert(dir, DirBucket { mtime, apps });
+        changed = true;
+    }
+
+    let before = index.dirs.len();
+    index.dirs.retain(|dir, _| live_dirs.contains(dir));
+    if index.dirs.len() != before {
+        changed = true;
+    }
+
+    if changed {
+        if let Err(err) = save(&index) {
+            eprintln!("Warning: failed to write application index: {err}");
+        }
+    }
+
+    index
+}
+
+/// Forces a full rebuild of the application index (`rsopen --reindex`).
+pub fn rebuild() -> Result<()> {
+    load_or_refresh(true);
+    Ok(())
+}
+
+/// Runs the fuzzy match against the cached application index, refreshing
+/// any stale directories first.
+pub(crate) fn best_match(query: &str) -> Option<SearchResult> {
+    let index = load_or_refresh(false);
+
+    let mut best: Option<(usize, &IndexedApp)> = None;
+    'search: for bucket in index.dirs.values() {
+        for app in &bucket.apps {
+            let score = if app.name == query {
+                0
+            } else if app.name.contains(query) {
+                levenshtein(&app.name, query)
+            } else {
+                continue;
+            };
+
+            let better = match &best {
+                Some((current_score, _)) => score < *current_score,
+                None => true,
+            };
+            if better {
+                best = Some((score, app));
+            }
+            if score == 0 {
+                break 'search;
+            }
+        }
+    }
+
+    best.map(|(score, app)| SearchResult {
+        path: app.path.clone(),
+        score,
+        exec: app.exec.clone(),
+        is_desktop: app.is_desktop,
+        terminal: app.terminal,
+        display_name: Some(app.display_name.clone()),
+        icon: app.icon.clone(),
+    })
+}
@@ -1,25 +1,79 @@
 use anyhow::{bail, Context, Result};
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use strsim::levenshtein;
 use walkdir::WalkDir;
 
+#[cfg(target_os = "linux")]
+mod desktop;
+mod index;
+mod path_resolve;
+mod reveal;
+mod runner;
+mod sandbox;
+#[cfg(target_os = "linux")]
+mod terminal;
+
+use runner::Runner;
+
 #[derive(Debug)]
 struct SearchResult {
     path: PathBuf,
     score: usize, // Lower is better (0 = exact)
     exec: Option<String>,
     is_desktop: bool,
+    /// Whether the entry should be run inside a terminal emulator
+    /// (`Terminal=true` for desktop entries).
+    terminal: bool,
+    /// Localized display name, used for `%c` field-code expansion.
+    display_name: Option<String>,
+    /// Icon name, used for `%i` field-code expansion.
+    icon: Option<String>,
+}
+
+/// Forces a full rebuild of the cached application index (`rsopen --reindex`).
+pub fn reindex() -> Result<()> {
+    index::rebuild()
 }
 
-/// Attempts to launch an application by its name.
-pub fn launch_app(app_name: &str, verbose: bool) -> Result<()> {
+/// Resolves `app_name` through the same search pipeline as [`launch_app`]
+/// and launches it with `target` (a file path or URL) as its argument
+/// (`rsopen --with <app> <target>`). For desktop entries this fills the
+/// `%f`/`%u`/`%F`/`%U` field codes instead of being appended verbatim.
+pub fn open_with(app_name: &str, target: &str, verbose: bool, force_terminal: bool) -> Result<()> {
+    launch_app(
+        app_name,
+        verbose,
+        std::slice::from_ref(&target.to_string()),
+        force_terminal,
+    )
+}
+
+/// Reveals `path` in the platform's file manager, selecting it where
+/// supported instead of just opening its containing directory
+/// (`rsopen --reveal <path>`).
+pub fn reveal(path: &str) -> Result<()> {
+    reveal::reveal(path)
+}
+
+/// Attempts to launch an application by its name, forwarding `args` to
+/// whichever spawn path ends up running it. `force_terminal` wraps the
+/// resolved command in a terminal emulator even if it isn't a desktop
+/// entry flagged `Terminal=true` (`rsopen --terminal <app>`).
+pub fn launch_app(
+    app_name: &str,
+    verbose: bool,
+    args: &[String],
+    force_terminal: bool,
+) -> Result<()> {
     if verbose {
         println!("Attempting to launch '{}'...", app_name);
     }
 
     // 1. Fast Path (Native)
-    if let Ok(()) = launch_app_native(app_name) {
+    // Skipped under --terminal on Linux: it spawns a $PATH hit directly,
+    // not inside a terminal emulator.
+    let skip_native = cfg!(target_os = "linux") && force_terminal;
+    if !skip_native && launch_app_native(app_name, args).is_ok() {
         if verbose {
             println!("Successfully launched '{}' using native command.", app_name);
         }
@@ -39,53 +93,75 @@ pub fn launch_app(app_name: &str, verbose: bool) -> Result<()> {
         None => best_candidate = Some(candidate),
     };
 
-    // 2. Desktop File Search (Linux only)
-    #[cfg(target_os = "linux")]
-    {
-        if verbose {
-            println!("Native launch failed. Searching desktop entries...");
-        }
-        if let Some(res) = search_desktop_entries(app_name, &query, verbose) {
-            if res.score == 0 {
-                if verbose {
-                    println!("Found exact desktop entry match.");
-                }
-                return launch_search_result(res);
+    // 2. Application Index (desktop entries + $PATH, cached on disk)
+    if verbose {
+        println!("Native launch failed. Searching the application index...");
+    }
+    let index_hit = index::best_match(&query);
+    let index_found_candidate = index_hit.is_some();
+    if let Some(res) = index_hit {
+        if res.score == 0 {
+            if verbose {
+                println!("Found exact index match.");
             }
-            update_best(res);
+            return launch_search_result(res, args, force_terminal);
         }
+        update_best(res);
     }
 
     if verbose {
-        println!("Searching common paths...");
+        println!("Searching $PATH...");
+    }
+
+    // 3. $PATH Resolution
+    if let Some(path) = path_resolve::resolve(app_name) {
+        if verbose {
+            println!("Found exact $PATH match.");
+        }
+        return launch_search_result(
+            SearchResult {
+                path,
+                score: 0,
+                exec: None,
+                is_desktop: false,
+                terminal: false,
+                display_name: None,
+                icon: None,
+            },
+            args,
+            force_terminal,
+        );
     }
 
-    // 3. Common Paths Search
+    // 3b. Common Paths Search — for app bundles and self-contained installs
+    // that $PATH resolution and the index don't cover (e.g. macOS .app
+    // bundles, Windows Program Files, /opt).
     let common_paths = get_common_paths();
     if let Some(res) = search_paths(&common_paths, app_name, &query) {
         if res.score == 0 {
             if verbose {
                 println!("Found exact common path match.");
             }
-            return launch_search_result(res);
+            return launch_search_result(res, args, force_terminal);
         }
         update_best(res);
     }
 
-    if verbose {
-        println!("Searching full filesystem...");
-    }
-
-    // 4. Full Search
-    let root = get_root_path();
-    if let Some(res) = search_recursive(root, app_name, &query, verbose) {
-        if res.score == 0 {
-            if verbose {
-                println!("Found exact filesystem match.");
+    // 4. Full Search — last resort, only when the index had nothing at all.
+    if !index_found_candidate {
+        if verbose {
+            println!("Searching full filesystem...");
+        }
+        let root = get_root_path();
+        if let Some(res) = search_recursive(root, app_name, &query, verbose) {
+            if res.score == 0 {
+                if verbose {
+                    println!("Found exact filesystem match.");
+                }
+                return launch_search_result(res, args, force_terminal);
             }
-            return launch_search_result(res);
+            update_best(res);
         }
-        update_best(res);
     }
 
     // If we are here, no exact match. Check fuzzy.
@@ -96,136 +172,37 @@ pub fn launch_app(app_name: &str, verbose: bool) -> Result<()> {
                 res.score
             );
         }
-        return launch_search_result(res);
+        return launch_search_result(res, args, force_terminal);
     }
 
     bail!("Could not find or launch application: {}", app_name);
 }
 
-fn launch_search_result(res: SearchResult) -> Result<()> {
+fn launch_search_result(res: SearchResult, args: &[String], force_terminal: bool) -> Result<()> {
+    let use_terminal = force_terminal || res.terminal;
+
     #[cfg(target_os = "linux")]
     if res.is_desktop {
-        if let Some(exec) = res.exec {
+        if let Some(exec) = res.exec.clone() {
             println!("Launching desktop entry: {:?} (Exec={})", res.path, exec);
-            return execute_desktop_entry(&exec);
+            return desktop::execute_desktop_entry(&exec, &res, args, use_terminal);
         }
     }
 
     println!("Launching: {:?}", res.path);
-    launch_executable(&res.path)
-}
-
-#[cfg(target_os = "linux")]
-fn execute_desktop_entry(exec: &str) -> Result<()> {
-    let parts: Vec<&str> = exec
-        .split_whitespace()
-        .filter(|p| !p.starts_with('%'))
-        .collect();
-    if parts.is_empty() {
-        bail!("Empty Exec line");
-    }
-
-    let cmd = parts[0];
-    let args = &parts[1..];
-
-    Command::new(cmd)
-        .args(args)
-        .spawn()
-        .context("Failed to spawn desktop entry command")?;
-
-    Ok(())
-}
-
-#[cfg(target_os = "linux")]
-fn search_desktop_entries(
-    _original_name: &str,
-    query: &str,
-    verbose: bool,
-) -> Option<SearchResult> {
-    use std::io::BufRead;
-
-    let dirs = [
-        PathBuf::from("/usr/share/applications"),
-        dirs::data_local_dir()
-            .map(|p| p.join("applications"))
-            .unwrap_or_else(|| PathBuf::from("~/.local/share/applications")),
-        PathBuf::from("/var/lib/flatpak/exports/share/applications"),
-        PathBuf::from("/snap/gui"),
-    ];
-
-    let mut best_res: Option<SearchResult> = None;
-
-    for dir in &dirs {
-        if !dir.exists() {
-            continue;
-        }
-
-        let walker = WalkDir::new(dir).max_depth(2).follow_links(true);
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if path.extension().is_some_and(|e| e == "desktop") {
-                if let Ok(file) = std::fs::File::open(path) {
-                    let reader = std::io::BufReader::new(file);
-                    let mut name_found: Option<String> = None;
-                    let mut exec_found: Option<String> = None;
-
-                    for line in reader.lines().map_while(Result::ok) {
-                        let line = line.trim();
-                        if line.starts_with("Name=") {
-                            name_found = Some(line.trim_start_matches("Name=").to_string());
-                        } else if line.starts_with("Exec=") {
-                            exec_found = Some(line.trim_start_matches("Exec=").to_string());
-                        }
-                    }
-
-                    if let (Some(name), Some(exec)) = (name_found, exec_found) {
-                        let name_lower = name.to_lowercase();
-
-                        let score = if name_lower == query {
-                            0
-                        } else if name_lower.contains(query) {
-                            levenshtein(&name_lower, query)
-                        } else {
-                            // Not a substring match, ignore
-                            continue;
-                        };
-
-                        let candidate = SearchResult {
-                            path: path.to_path_buf(),
-                            score,
-                            exec: Some(exec),
-                            is_desktop: true,
-                        };
-
-                        if verbose {
-                            println!("Desktop entry found: {:?}", candidate);
-                        }
-
-                        match best_res {
-                            Some(ref current) => {
-                                if score < current.score {
-                                    best_res = Some(candidate);
-                                }
-                            }
-                            None => best_res = Some(candidate),
-                        }
-
-                        if score == 0 {
-                            return best_res;
-                        }
-                    }
-                }
-            }
-        }
-    }
-    best_res
+    launch_executable(&res.path, args, use_terminal)
 }
 
 #[cfg(target_os = "windows")]
-fn launch_app_native(app_name: &str) -> Result<()> {
-    let output = Command::new("cmd")
-        .args(["/C", "start", "", app_name])
-        .output()?;
+fn launch_app_native(app_name: &str, args: &[String]) -> Result<()> {
+    let mut cmd_args = vec![
+        "/C".to_string(),
+        "start".to_string(),
+        String::new(),
+        app_name.to_string(),
+    ];
+    cmd_args.extend(args.iter().cloned());
+    let output = Runner::new("cmd").args(cmd_args).run_captured()?;
     if output.status.success() {
         Ok(())
     } else {
@@ -234,8 +211,13 @@ fn launch_app_native(app_name: &str) -> Result<()> {
 }
 
 #[cfg(target_os = "macos")]
-fn launch_app_native(app_name: &str) -> Result<()> {
-    let output = Command::new("open").args(["-a", app_name]).output()?;
+fn launch_app_native(app_name: &str, args: &[String]) -> Result<()> {
+    let mut cmd_args = vec!["-a".to_string(), app_name.to_string()];
+    if !args.is_empty() {
+        cmd_args.push("--args".to_string());
+        cmd_args.extend(args.iter().cloned());
+    }
+    let output = Runner::new("open").args(cmd_args).run_captured()?;
     if output.status.success() {
         Ok(())
     } else {
@@ -244,24 +226,37 @@ fn launch_app_native(app_name: &str) -> Result<()> {
 }
 
 #[cfg(target_os = "linux")]
-fn launch_app_native(app_name: &str) -> Result<()> {
-    let output = Command::new(app_name).output();
-    match output {
-        Ok(o) if o.status.success() => Ok(()),
-        _ => bail!("Failed to launch on Linux"),
-    }
+fn launch_app_native(app_name: &str, args: &[String]) -> Result<()> {
+    // Resolve first so we never block on `.output()` waiting for a
+    // long-running GUI app to exit before we know whether to fall through.
+    let path = path_resolve::resolve(app_name).context("Not found on $PATH")?;
+    Runner::new(path)
+        .args(args)
+        .stdio(runner::StdioMode::Null)
+        .normalize_env()
+        .spawn_detached()
 }
 
 #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-fn launch_app_native(_: &str) -> Result<()> {
+fn launch_app_native(_: &str, _: &[String]) -> Result<()> {
     bail!("Unsupported platform")
 }
 
-fn launch_executable(path: &Path) -> Result<()> {
+fn launch_executable(path: &Path, args: &[String], use_terminal: bool) -> Result<()> {
+    let _ = use_terminal; // only consulted on Linux, below
+
     #[cfg(target_os = "macos")]
     {
         if path.extension().map_or(false, |ext| ext == "app") {
-            let output = Command::new("open").arg(path).output()?;
+            let mut cmd_args = vec!["-a".to_string(), path.to_string_lossy().to_string()];
+            if !args.is_empty() {
+                cmd_args.push("--args".to_string());
+                cmd_args.extend(args.iter().cloned());
+            }
+            let output = Runner::new("open")
+                .args(cmd_args)
+                .normalize_env()
+                .run_captured()?;
             if output.status.success() {
                 return Ok(());
             }
@@ -270,9 +265,17 @@ fn launch_executable(path: &Path) -> Result<()> {
 
     #[cfg(target_os = "windows")]
     {
-        let output = Command::new("cmd")
-            .args(["/C", "start", "", &path.to_string_lossy()])
-            .output()?;
+        let mut cmd_args = vec![
+            "/C".to_string(),
+            "start".to_string(),
+            String::new(),
+            path.to_string_lossy().to_string(),
+        ];
+        cmd_args.extend(args.iter().cloned());
+        let output = Runner::new("cmd")
+            .args(cmd_args)
+            .normalize_env()
+            .run_captured()?;
         if output.status.success() {
             return Ok(());
         }
@@ -280,31 +283,54 @@ fn launch_executable(path: &Path) -> Result<()> {
 
     #[cfg(target_os = "linux")]
     {
-        let output = Command::new("xdg-open").arg(path).output();
+        if use_terminal {
+            let emulator = terminal::TerminalEmulator::detect()
+                .context("--terminal was given, but no terminal emulator could be found")?;
+            let argv = emulator.wrap(&path.to_string_lossy(), args);
+            return Runner::new(&argv[0])
+                .args(&argv[1..])
+                .normalize_env()
+                .spawn_detached();
+        }
+
+        let mut xdg_args = vec![path.to_string_lossy().to_string()];
+        xdg_args.extend(args.iter().cloned());
+        let output = Runner::new("xdg-open")
+            .args(xdg_args)
+            .normalize_env()
+            .run_captured();
         if let Ok(o) = output {
             if o.status.success() {
                 return Ok(());
             }
         }
 
-        if Command::new(path).spawn().is_ok() {
+        if Runner::new(path)
+            .args(args)
+            .normalize_env()
+            .spawn_detached()
+            .is_ok()
+        {
             return Ok(());
         }
 
         // Fallback to sh
-        Command::new("sh")
-            .arg(path)
-            .spawn()
+        let mut sh_args = vec![path.to_string_lossy().to_string()];
+        sh_args.extend(args.iter().cloned());
+        Runner::new("sh")
+            .args(sh_args)
+            .normalize_env()
+            .spawn_detached()
             .context("Failed to spawn executable (tried xdg-open, direct execution, and sh)")?;
         Ok(())
     }
 
     #[cfg(not(target_os = "linux"))]
     {
-        Command::new(path)
-            .spawn()
-            .context("Failed to spawn executable")?;
-        Ok(())
+        Runner::new(path)
+            .args(args)
+            .normalize_env()
+            .spawn_detached()
     }
 }
 
@@ -323,13 +349,10 @@ fn get_common_paths() -> Vec<&'static str> {
     }
     #[cfg(target_os = "linux")]
     {
-        vec![
-            "/usr/bin",
-            "/usr/local/bin",
-            "/opt",
-            "/snap/bin",
-            "/var/lib/flatpak/exports/bin",
-        ]
+        // Real binary directories are now covered by $PATH resolution and
+        // the application index; `/opt` is the one common spot for
+        // self-contained installs that don't add themselves to $PATH.
+        vec!["/opt"]
     }
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
@@ -363,6 +386,9 @@ fn search_paths(paths: &[&str], _original_name: &str, query: &str) -> Option<Sea
                     score,
                     exec: None,
                     is_desktop: false,
+                    terminal: false,
+                    display_name: None,
+                    icon: None,
                 };
                 match best_res {
                     Some(ref current) => {
@@ -418,6 +444,9 @@ fn search_recursive(
                         score,
                         exec: None,
                         is_desktop: false,
+                        terminal: false,
+                        display_name: None,
+                        icon: None,
                     };
                     match best_res {
                         Some(ref current) => {
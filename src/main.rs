@@ -1,23 +1,72 @@
 use anyhow::Result;
 use clap::Parser;
-use rsopen::launch_app;
+use rsopen::{launch_app, open_with, reindex, reveal};
 
 #[derive(Parser, Debug)]
 #[command(version, about = "A multiplatform app launcher", long_about = None)]
 struct Args {
-    /// Name of the application to launch
-    #[arg(index = 1)]
-    app_name: String,
+    /// Name of the application to launch, or (with `--with`) the file/URL to open with it
+    #[arg(index = 1, required_unless_present_any = ["reindex", "reveal"])]
+    app_name: Option<String>,
 
     /// Enable verbose output
     #[arg(short, long)]
     verbose: bool,
+
+    /// Rebuild the cached application index from scratch and exit
+    #[arg(long)]
+    reindex: bool,
+
+    /// Open the file/URL with this app instead of launching by name
+    #[arg(long, value_name = "APP")]
+    with: Option<String>,
+
+    /// Reveal this path in the file manager instead of launching anything
+    #[arg(long, value_name = "PATH", conflicts_with = "with")]
+    reveal: Option<String>,
+
+    /// Launch inside a terminal emulator, even if the resolved app isn't a
+    /// `Terminal=true` desktop entry
+    #[arg(long)]
+    terminal: bool,
+
+    /// Arguments to forward to the launched application (e.g. `rsopen code -- ~/project`)
+    #[arg(last = true)]
+    trailing_args: Vec<String>,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    if let Err(e) = launch_app(&args.app_name, args.verbose) {
+    if args.reindex {
+        reindex()?;
+        println!("Application index rebuilt.");
+        return Ok(());
+    }
+
+    if let Some(path) = args.reveal {
+        if let Err(e) = reveal(&path) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(app) = args.with {
+        let target = args
+            .app_name
+            .expect("clap guarantees app_name is present with --with");
+        if let Err(e) = open_with(&app, &target, args.verbose, args.terminal) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let app_name = args
+        .app_name
+        .expect("clap guarantees app_name is present without --reindex/--reveal");
+    if let Err(e) = launch_app(&app_name, args.verbose, &args.trailing_args, args.terminal) {
         eprintln!("Error: {}", e);
         std::process::exit(1);
     }
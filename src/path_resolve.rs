@@ -0,0 +1,158 @@
+//! Resolving executables from `$PATH`, shared by the fast `$PATH` lookup in
+//! `launch_app`, the application index, and the desktop-entry `TryExec`
+//! check.
+
+use std::path::{Path, PathBuf};
+
+/// Splits `$PATH` on the platform separator (`:` on Unix, `;` on Windows)
+/// into its component directories.
+pub(crate) fn path_dirs() -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|p| std::env::split_paths(&p).collect())
+        .unwrap_or_default()
+}
+
+/// Resolves `name` to an executable file on `$PATH`. If `name` already
+/// contains a path separator it's checked directly instead. On Windows,
+/// each `PATHEXT` suffix is tried when `name` has no extension of its own.
+pub(crate) fn resolve(name: &str) -> Option<PathBuf> {
+    let candidate = Path::new(name);
+    if name.contains(std::path::MAIN_SEPARATOR) {
+        return is_executable(candidate).then(|| candidate.to_path_buf());
+    }
+
+    for dir in path_dirs() {
+        for variant in name_variants(name) {
+            let full = dir.join(&variant);
+            if is_executable(&full) {
+                return Some(full);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(windows)]
+fn pathext_list() -> Vec<String> {
+    std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| ext.trim_start_matches('.').to_string())
+        .collect()
+}
+
+#[cfg(windows)]
+fn name_variants(name: &str) -> Vec<String> {
+    if Path::new(name).extension().is_some() {
+        return vec![name.to_string()];
+    }
+    pathext_list()
+        .into_iter()
+        .map(|ext| format!("{name}.{ext}"))
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn name_variants(name: &str) -> Vec<String> {
+    vec![name.to_string()]
+}
+
+#[cfg(unix)]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// On Windows, a bare `path.is_file()` would treat every regular file in a
+/// bin directory (DLLs, READMEs, …) as launchable, so this also requires
+/// the extension to be one of `PATHEXT`'s (matching how the shell decides
+/// what's runnable without an explicit extension).
+#[cfg(windows)]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    if !path.is_file() {
+        return false;
+    }
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return false;
+    };
+    pathext_list().iter().any(|e| e.eq_ignore_ascii_case(ext))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(windows))]
+    #[test]
+    fn name_variants_is_the_name_unchanged_off_windows() {
+        assert_eq!(name_variants("rsopen"), vec!["rsopen".to_string()]);
+    }
+
+    #[cfg(windows)]
+    mod windows {
+        use super::*;
+        use std::sync::Mutex;
+
+        // `PATHEXT` is process-global, so tests that mutate it are
+        // serialized against each other rather than racing under
+        // `cargo test`'s default parallel test execution.
+        static PATHEXT_LOCK: Mutex<()> = Mutex::new(());
+
+        #[test]
+        fn name_variants_keeps_an_existing_extension() {
+            let _guard = PATHEXT_LOCK.lock().unwrap();
+            assert_eq!(name_variants("app.exe"), vec!["app.exe".to_string()]);
+        }
+
+        #[test]
+        fn name_variants_appends_each_pathext_suffix() {
+            let _guard = PATHEXT_LOCK.lock().unwrap();
+            std::env::set_var("PATHEXT", ".EXE;.BAT");
+            let variants = name_variants("app");
+            std::env::remove_var("PATHEXT");
+            assert_eq!(variants, vec!["app.EXE".to_string(), "app.BAT".to_string()]);
+        }
+
+        #[test]
+        fn is_executable_rejects_extensions_outside_pathext() {
+            let _guard = PATHEXT_LOCK.lock().unwrap();
+            std::env::set_var("PATHEXT", ".EXE");
+
+            let dir = std::env::temp_dir();
+            let readme = dir.join("rsopen_path_resolve_test_readme.txt");
+            std::fs::write(&readme, b"not launchable").unwrap();
+
+            let result = is_executable(&readme);
+
+            std::fs::remove_file(&readme).unwrap();
+            std::env::remove_var("PATHEXT");
+
+            assert!(!result);
+        }
+
+        #[test]
+        fn is_executable_accepts_extensions_in_pathext() {
+            let _guard = PATHEXT_LOCK.lock().unwrap();
+            std::env::set_var("PATHEXT", ".EXE");
+
+            let dir = std::env::temp_dir();
+            let exe = dir.join("rsopen_path_resolve_test_app.exe");
+            std::fs::write(&exe, b"not a real binary, just a stand-in file").unwrap();
+
+            let result = is_executable(&exe);
+
+            std::fs::remove_file(&exe).unwrap();
+            std::env::remove_var("PATHEXT");
+
+            assert!(result);
+        }
+    }
+}
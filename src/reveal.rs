@@ -0,0 +1,87 @@
+//! "Reveal in file manager" support: opens the directory containing a path
+//! and, where the desktop environment supports it, selects the file itself
+//! instead of just landing on its parent folder.
+
+use crate::runner::Runner;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Reveals `path` in the platform's file manager, selecting it if possible.
+pub(crate) fn reveal(path: &str) -> Result<()> {
+    let path = Path::new(path);
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    reveal_platform(&path)
+}
+
+#[cfg(target_os = "windows")]
+fn reveal_platform(path: &Path) -> Result<()> {
+    let arg = format!("/select,{}", path.display());
+    Runner::new("explorer").args([arg]).spawn_detached()
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_platform(path: &Path) -> Result<()> {
+    Runner::new("open")
+        .args(["-R", &path.to_string_lossy()])
+        .spawn_detached()
+}
+
+#[cfg(target_os = "linux")]
+fn reveal_platform(path: &Path) -> Result<()> {
+    if show_items_dbus(path).is_ok() {
+        return Ok(());
+    }
+
+    // No file manager answered on D-Bus; fall back to opening the
+    // containing directory instead of the file itself.
+    let parent = path.parent().unwrap_or(path);
+    Runner::new("xdg-open")
+        .args([parent.to_string_lossy().to_string()])
+        .normalize_env()
+        .spawn_detached()
+}
+
+/// Asks the file manager to select `path` via the freedesktop.org
+/// `org.freedesktop.FileManager1.ShowItems` D-Bus method.
+#[cfg(target_os = "linux")]
+fn show_items_dbus(path: &Path) -> Result<()> {
+    use dbus::blocking::Connection;
+    use std::time::Duration;
+
+    let uri = format!("file://{}", percent_encode_path(&path.to_string_lossy()));
+    let conn = Connection::new_session().context("Failed to connect to session D-Bus")?;
+    let proxy = conn.with_proxy(
+        "org.freedesktop.FileManager1",
+        "/org/freedesktop/FileManager1",
+        Duration::from_secs(5),
+    );
+    proxy
+        .method_call::<(), _, _, _>(
+            "org.freedesktop.FileManager1",
+            "ShowItems",
+            (vec![uri], String::new()),
+        )
+        .context("ShowItems D-Bus call failed")
+}
+
+/// Percent-encodes everything outside the URI "unreserved" set, leaving `/`
+/// intact so path separators survive, for building the `file://` URI passed
+/// over D-Bus to `ShowItems`.
+#[cfg(target_os = "linux")]
+fn percent_encode_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                out.push(byte as char);
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn reveal_platform(_path: &Path) -> Result<()> {
+    anyhow::bail!("Unsupported platform")
+}
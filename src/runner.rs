@@ -0,0 +1,107 @@
+//! A small builder around `std::process::Command` standardizing how rsopen
+//! spawns child processes: trailing arguments, stdio handling, and
+//! sandbox-aware environment normalization (see `crate::sandbox`), with
+//! either a detached spawn or an output-capturing run — instead of each
+//! launch path picking its own mix of `.spawn()` and `.output()`.
+
+use anyhow::{Context, Result};
+use std::ffi::OsStr;
+use std::process::{Command, Output, Stdio};
+
+/// How a detached child's stdout/stderr should be connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum StdioMode {
+    /// Inherit the parent's stdout/stderr, so the launched program's
+    /// output (if any) is visible to the user. The default.
+    #[default]
+    Inherit,
+    /// Discard the child's stdout/stderr.
+    Null,
+}
+
+/// The environment a spawned child inherits.
+#[derive(Debug, Clone)]
+enum BaseEnv {
+    /// Inherit rsopen's own environment as-is.
+    Inherit,
+    /// Replace it wholesale with a specific set of variables (used to hand
+    /// sandboxed bundles' children a host-equivalent environment).
+    Replace(Vec<(String, String)>),
+}
+
+pub(crate) struct Runner {
+    program: std::ffi::OsString,
+    args: Vec<std::ffi::OsString>,
+    stdio: StdioMode,
+    base_env: BaseEnv,
+}
+
+impl Runner {
+    pub(crate) fn new(program: impl AsRef<OsStr>) -> Self {
+        Self {
+            program: program.as_ref().to_os_string(),
+            args: Vec::new(),
+            stdio: StdioMode::default(),
+            base_env: BaseEnv::Inherit,
+        }
+    }
+
+    /// Replaces the inherited environment with a host-equivalent one when
+    /// rsopen is itself running inside an AppImage, snap, or Flatpak, so
+    /// bundle-only `LD_LIBRARY_PATH`/`PYTHONPATH`/etc. don't leak into the
+    /// launched program. A no-op outside a recognized sandbox.
+    pub(crate) fn normalize_env(mut self) -> Self {
+        if let Some(host_env) = crate::sandbox::host_env() {
+            self.base_env = BaseEnv::Replace(host_env);
+        }
+        self
+    }
+
+    pub(crate) fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|a| a.as_ref().to_os_string()));
+        self
+    }
+
+    pub(crate) fn stdio(mut self, stdio: StdioMode) -> Self {
+        self.stdio = stdio;
+        self
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        if let BaseEnv::Replace(vars) = &self.base_env {
+            cmd.env_clear();
+            cmd.envs(vars.iter().cloned());
+        }
+        cmd.args(&self.args);
+        cmd
+    }
+
+    /// Spawns the process without waiting for it, detached from rsopen.
+    pub(crate) fn spawn_detached(&self) -> Result<()> {
+        let mut cmd = self.command();
+        match self.stdio {
+            StdioMode::Inherit => {
+                cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+            }
+            StdioMode::Null => {
+                cmd.stdout(Stdio::null()).stderr(Stdio::null());
+            }
+        }
+        cmd.spawn()
+            .with_context(|| format!("Failed to spawn {:?}", self.program))?;
+        Ok(())
+    }
+
+    /// Runs the process to completion, capturing its output.
+    pub(crate) fn run_captured(&self) -> Result<Output> {
+        self.command()
+            .output()
+            .with_context(|| format!("Failed to run {:?}", self.program))
+    }
+}
@@ -0,0 +1,152 @@
+//! Detects whether rsopen itself is running from inside an AppImage, Snap,
+//! or Flatpak bundle, and builds a host-equivalent environment for spawned
+//! children so bundle-only library/plugin paths don't leak into — and
+//! break — the applications rsopen launches.
+
+use std::collections::HashSet;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Environment variables that hold an OS-style path list, and so may carry
+/// bundle-internal entries that need scrubbing before being handed to a
+/// spawned child.
+const PATH_LIST_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "XDG_DATA_DIRS",
+    "PYTHONPATH",
+    "GST_PLUGIN_PATH",
+    "GST_PLUGIN_SYSTEM_PATH",
+];
+
+pub(crate) fn is_appimage() -> bool {
+    env::var_os("APPIMAGE").is_some() || env::var_os("APPDIR").is_some()
+}
+
+pub(crate) fn is_snap() -> bool {
+    env::var_os("SNAP").is_some()
+}
+
+pub(crate) fn is_flatpak() -> bool {
+    env::var_os("FLATPAK_ID").is_some()
+}
+
+/// The bundle root to scrub path-list entries against: `$APPDIR` for
+/// AppImages (falling back to the directory containing `$APPIMAGE` if only
+/// that's set), `$SNAP` for snaps, or Flatpak's well-known sandbox mount
+/// point otherwise.
+fn bundle_root() -> Option<PathBuf> {
+    if is_appimage() {
+        if let Some(dir) = env::var_os("APPDIR") {
+            return Some(PathBuf::from(dir));
+        }
+        if let Some(appimage) = env::var_os("APPIMAGE") {
+            let appimage = PathBuf::from(appimage);
+            return Some(
+                appimage
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or(appimage),
+            );
+        }
+    }
+    if is_snap() {
+        if let Some(dir) = env::var_os("SNAP") {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    if is_flatpak() {
+        return Some(PathBuf::from("/app"));
+    }
+    None
+}
+
+/// Builds a host-equivalent environment for spawned children: path-list
+/// variables have bundle-internal entries dropped (order preserved,
+/// de-duplicated), and any that would become empty are unset entirely
+/// rather than exported as an empty string. Returns `None` when rsopen
+/// isn't running inside a recognized bundle, so callers can skip
+/// rebuilding the environment altogether.
+pub(crate) fn host_env() -> Option<Vec<(String, String)>> {
+    let root = bundle_root()?;
+
+    let mut vars = Vec::new();
+    for (key, value) in env::vars() {
+        if PATH_LIST_VARS.contains(&key.as_str()) {
+            if let Some(cleaned) = clean_path_list(&value, &root) {
+                vars.push((key, cleaned));
+            }
+        } else {
+            vars.push((key, value));
+        }
+    }
+    Some(vars)
+}
+
+/// Drops entries of a `PATH`-style variable that live inside `root`,
+/// preserving order and removing duplicates. Returns `None` if the
+/// result would be empty.
+fn clean_path_list(value: &str, root: &Path) -> Option<String> {
+    let mut seen = HashSet::new();
+    let mut kept = Vec::new();
+
+    for dir in env::split_paths(value) {
+        if dir.starts_with(root) {
+            continue;
+        }
+        let dir = dir.to_string_lossy().into_owned();
+        if seen.insert(dir.clone()) {
+            kept.push(dir);
+        }
+    }
+
+    if kept.is_empty() {
+        return None;
+    }
+
+    env::join_paths(&kept)
+        .ok()
+        .map(|joined| joined.to_string_lossy().into_owned())
+        .filter(|joined| !joined.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn join(paths: &[&str]) -> String {
+        env::join_paths(paths.iter().map(PathBuf::from))
+            .unwrap()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn split(value: &str) -> Vec<String> {
+        env::split_paths(value)
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn clean_path_list_drops_entries_inside_root() {
+        let root = Path::new("/opt/MyApp.AppDir");
+        let value = join(&["/opt/MyApp.AppDir/usr/bin", "/usr/bin", "/usr/local/bin"]);
+        let cleaned = clean_path_list(&value, root).unwrap();
+        assert_eq!(split(&cleaned), vec!["/usr/bin", "/usr/local/bin"]);
+    }
+
+    #[test]
+    fn clean_path_list_preserves_order_and_dedups() {
+        let root = Path::new("/opt/Bundle");
+        let value = join(&["/usr/bin", "/usr/local/bin", "/usr/bin"]);
+        let cleaned = clean_path_list(&value, root).unwrap();
+        assert_eq!(split(&cleaned), vec!["/usr/bin", "/usr/local/bin"]);
+    }
+
+    #[test]
+    fn clean_path_list_returns_none_when_everything_is_bundle_internal() {
+        let root = Path::new("/opt/Bundle");
+        let value = join(&["/opt/Bundle/bin", "/opt/Bundle/usr/bin"]);
+        assert!(clean_path_list(&value, root).is_none());
+    }
+}
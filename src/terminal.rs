@@ -0,0 +1,74 @@
+//! Terminal-emulator detection and command wrapping, so `Terminal=true`
+//! desktop entries (or any executable, via `--terminal`) run in a visible
+//! terminal window instead of flashing and exiting headless.
+
+use std::path::PathBuf;
+
+/// How a terminal emulator expects the command it should run to be passed.
+#[derive(Debug, Clone, Copy)]
+enum ExecConvention {
+    /// `<emulator> -e <command> <args...>`
+    DashE,
+    /// `<emulator> -- <command> <args...>`
+    DoubleDash,
+    /// `<emulator> <command> <args...>`, no separating flag.
+    Direct,
+}
+
+/// Terminal emulators to probe, in priority order, paired with the
+/// convention each uses to take the command to run.
+const CANDIDATES: &[(&str, ExecConvention)] = &[
+    ("x-terminal-emulator", ExecConvention::DashE),
+    ("gnome-terminal", ExecConvention::DoubleDash),
+    ("konsole", ExecConvention::DashE),
+    ("alacritty", ExecConvention::DashE),
+    ("kitty", ExecConvention::Direct),
+    ("xterm", ExecConvention::DashE),
+];
+
+/// A resolved terminal emulator: its executable path and how to pass it
+/// the command to run inside it.
+pub(crate) struct TerminalEmulator {
+    program: PathBuf,
+    convention: ExecConvention,
+}
+
+impl TerminalEmulator {
+    /// Probes `$TERMINAL` first, then a prioritized list of common
+    /// emulators resolvable on `$PATH`.
+    pub(crate) fn detect() -> Option<Self> {
+        if let Ok(term) = std::env::var("TERMINAL") {
+            if let Some(program) = crate::path_resolve::resolve(&term) {
+                return Some(Self {
+                    program,
+                    convention: ExecConvention::DashE,
+                });
+            }
+        }
+
+        for (name, convention) in CANDIDATES {
+            if let Some(program) = crate::path_resolve::resolve(name) {
+                return Some(Self {
+                    program,
+                    convention: *convention,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Builds the full argv to run `command` (with `args`) inside this
+    /// terminal emulator.
+    pub(crate) fn wrap(&self, command: &str, args: &[String]) -> Vec<String> {
+        let mut argv = vec![self.program.to_string_lossy().into_owned()];
+        match self.convention {
+            ExecConvention::DashE => argv.push("-e".to_string()),
+            ExecConvention::DoubleDash => argv.push("--".to_string()),
+            ExecConvention::Direct => {}
+        }
+        argv.push(command.to_string());
+        argv.extend(args.iter().cloned());
+        argv
+    }
+}